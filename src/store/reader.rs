@@ -9,21 +9,405 @@ use crate::store::index::Checkpoint;
 use crate::DocId;
 use lru::LruCache;
 use tantivy_fst::Ulen;
+use std::collections::{HashMap, HashSet};
 use std::io;
 use std::mem::size_of;
+use std::ops::Range;
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-const LRU_CACHE_CAPACITY: Ulen = 100;
+/// Default byte budget for the decompressed block cache, used when a
+/// [`StoreReader`] is opened via [`StoreReader::open`].
+///
+/// Blocks vary wildly in decompressed size (a few KiB to many MiB), so
+/// bounding the cache by block *count* makes memory usage unpredictable.
+/// Bounding it by bytes instead makes it deterministic.
+const DEFAULT_CACHE_BUDGET_BYTES: Ulen = 50 * 1024 * 1024;
 
 type Block = Arc<Vec<u8>>;
 
-type BlockCache = Arc<Mutex<LruCache<u64, Block>>>;
+/// How the store's decompressed block cache chooses which block to evict
+/// once it is over its byte budget.
+///
+/// Document-store access patterns are often skewed towards a small set of
+/// "hot" documents, so pure recency can evict a hot block that simply
+/// hasn't been touched in a while; `Lfu` and `WeightedLfu` trade that for
+/// tracking per-block access frequency instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheEvictionPolicy {
+    /// Evict the least-recently-used block. The default.
+    Lru,
+    /// Evict the block with the lowest access frequency.
+    Lfu,
+    /// Evict by `frequency / block_len`, so a small, frequently-hit block
+    /// outlives a large block that is hit just as often.
+    WeightedLfu,
+}
+
+impl Default for CacheEvictionPolicy {
+    fn default() -> Self {
+        CacheEvictionPolicy::Lru
+    }
+}
+
+/// A cache eviction policy owns the order in which blocks are evicted; the
+/// byte-budget bookkeeping in [`BlockCache`] stays the same regardless of
+/// which strategy is plugged in.
+trait EvictionStrategy: Send {
+    /// Records an access to `key` and returns its block, if cached.
+    fn get(&mut self, key: u64) -> Option<Block>;
+    /// Reports whether `key` is cached without recording an access, so
+    /// callers that only want to test membership don't skew frequency-based
+    /// policies as a side effect.
+    fn contains_key(&self, key: u64) -> bool;
+    /// Inserts `block` under `key`, returning the size in bytes of any
+    /// block it replaced.
+    fn insert(&mut self, key: u64, block: Block) -> Option<Ulen>;
+    /// Evicts and returns a single block, chosen by the policy.
+    fn evict_one(&mut self) -> Option<(u64, Block)>;
+    fn len(&self) -> usize;
+    #[cfg(test)]
+    fn peek_evict_candidate(&self) -> Option<u64>;
+}
+
+struct LruStrategy {
+    lru: LruCache<u64, Block>,
+}
+
+impl EvictionStrategy for LruStrategy {
+    fn get(&mut self, key: u64) -> Option<Block> {
+        self.lru.get(&key).cloned()
+    }
+
+    fn contains_key(&self, key: u64) -> bool {
+        self.lru.contains(&key)
+    }
+
+    fn insert(&mut self, key: u64, block: Block) -> Option<Ulen> {
+        self.lru.put(key, block).map(|evicted| evicted.len() as Ulen)
+    }
+
+    fn evict_one(&mut self) -> Option<(u64, Block)> {
+        self.lru.pop_lru()
+    }
+
+    fn len(&self) -> usize {
+        self.lru.len()
+    }
+
+    #[cfg(test)]
+    fn peek_evict_candidate(&self) -> Option<u64> {
+        self.lru.peek_lru().map(|(&key, _)| key)
+    }
+}
+
+struct LfuEntry {
+    block: Block,
+    frequency: u64,
+}
+
+/// Frequency-bucketed LFU: `freq_buckets[f]` holds every key whose access
+/// count is `f`, and `min_frequency` always points at a non-empty bucket,
+/// so picking the plain `Lfu` victim is O(1); removing it rescans the
+/// bucket keys when it empties the minimum bucket. `weighted` switches eviction to scan
+/// for the lowest `frequency / block_len` instead, per
+/// [`CacheEvictionPolicy::WeightedLfu`].
+struct LfuStrategy {
+    entries: HashMap<u64, LfuEntry>,
+    freq_buckets: HashMap<u64, HashSet<u64>>,
+    min_frequency: u64,
+    weighted: bool,
+}
+
+impl LfuStrategy {
+    fn new(weighted: bool) -> LfuStrategy {
+        LfuStrategy {
+            entries: HashMap::new(),
+            freq_buckets: HashMap::new(),
+            min_frequency: 0,
+            weighted,
+        }
+    }
+
+    /// Moves `key` from its current frequency bucket to the next one up.
+    fn bump_frequency(&mut self, key: u64) {
+        let frequency = match self.entries.get(&key) {
+            Some(entry) => entry.frequency,
+            None => return,
+        };
+        if let Some(bucket) = self.freq_buckets.get_mut(&frequency) {
+            bucket.remove(&key);
+            if bucket.is_empty() {
+                self.freq_buckets.remove(&frequency);
+                if self.min_frequency == frequency {
+                    self.min_frequency += 1;
+                }
+            }
+        }
+        let new_frequency = frequency + 1;
+        self.freq_buckets.entry(new_frequency).or_insert_with(HashSet::new).insert(key);
+        self.entries.get_mut(&key).unwrap().frequency = new_frequency;
+    }
+
+    fn remove(&mut self, key: u64) -> Option<LfuEntry> {
+        let entry = self.entries.remove(&key)?;
+        if let Some(bucket) = self.freq_buckets.get_mut(&entry.frequency) {
+            bucket.remove(&key);
+            if bucket.is_empty() {
+                self.freq_buckets.remove(&entry.frequency);
+                // Unlike `bump_frequency`, the removed key isn't landing in
+                // another bucket we know is non-empty, so when it emptied
+                // the minimum bucket we have to actually recompute the new
+                // minimum rather than just incrementing by one.
+                if self.min_frequency == entry.frequency {
+                    self.min_frequency = self.freq_buckets.keys().min().copied().unwrap_or(0);
+                }
+            }
+        }
+        Some(entry)
+    }
+}
+
+impl EvictionStrategy for LfuStrategy {
+    fn get(&mut self, key: u64) -> Option<Block> {
+        if !self.entries.contains_key(&key) {
+            return None;
+        }
+        self.bump_frequency(key);
+        self.entries.get(&key).map(|entry| entry.block.clone())
+    }
+
+    fn contains_key(&self, key: u64) -> bool {
+        self.entries.contains_key(&key)
+    }
+
+    fn insert(&mut self, key: u64, block: Block) -> Option<Ulen> {
+        let previous_bytes = self.remove(key).map(|entry| entry.block.len() as Ulen);
+        self.entries.insert(key, LfuEntry { block, frequency: 0 });
+        self.bump_frequency(key);
+        self.min_frequency = 1;
+        previous_bytes
+    }
+
+    fn evict_one(&mut self) -> Option<(u64, Block)> {
+        let victim = if self.weighted {
+            self.entries
+                .iter()
+                .min_by(|(_, a), (_, b)| {
+                    let score_a = a.frequency as f64 / a.block.len().max(1) as f64;
+                    let score_b = b.frequency as f64 / b.block.len().max(1) as f64;
+                    score_a
+                        .partial_cmp(&score_b)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .map(|(&key, _)| key)
+        } else {
+            self.freq_buckets
+                .get(&self.min_frequency)
+                .and_then(|bucket| bucket.iter().next().copied())
+        }?;
+        self.remove(victim).map(|entry| (victim, entry.block))
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[cfg(test)]
+    fn peek_evict_candidate(&self) -> Option<u64> {
+        if self.weighted {
+            self.entries
+                .iter()
+                .min_by(|(_, a), (_, b)| {
+                    let score_a = a.frequency as f64 / a.block.len().max(1) as f64;
+                    let score_b = b.frequency as f64 / b.block.len().max(1) as f64;
+                    score_a
+                        .partial_cmp(&score_b)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .map(|(&key, _)| key)
+        } else {
+            self.freq_buckets
+                .get(&self.min_frequency)
+                .and_then(|bucket| bucket.iter().next().copied())
+        }
+    }
+}
+
+/// A cache of decompressed blocks, bounded by total byte size rather than
+/// by number of entries, with a pluggable [`CacheEvictionPolicy`].
+///
+/// Eviction and insertion happen together while the cache's mutex is held,
+/// so the tracked `current_bytes` never drifts from the blocks actually
+/// stored. A block larger than the whole budget is returned read-through,
+/// without being inserted, so a single oversized block can never wedge the
+/// cache into evicting everything else forever.
+struct BlockCache {
+    strategy: Box<dyn EvictionStrategy>,
+    current_bytes: Ulen,
+    budget_bytes: Ulen,
+}
+
+impl BlockCache {
+    fn new(budget_bytes: Ulen, policy: CacheEvictionPolicy) -> BlockCache {
+        let strategy: Box<dyn EvictionStrategy> = match policy {
+            CacheEvictionPolicy::Lru => Box::new(LruStrategy {
+                lru: LruCache::unbounded(),
+            }),
+            CacheEvictionPolicy::Lfu => Box::new(LfuStrategy::new(false)),
+            CacheEvictionPolicy::WeightedLfu => Box::new(LfuStrategy::new(true)),
+        };
+        BlockCache {
+            strategy,
+            current_bytes: 0,
+            budget_bytes,
+        }
+    }
+
+    fn get(&mut self, key: &u64) -> Option<Block> {
+        self.strategy.get(*key)
+    }
+
+    /// Tests membership without bumping frequency counters, unlike `get`.
+    fn contains_key(&self, key: &u64) -> bool {
+        self.strategy.contains_key(*key)
+    }
+
+    fn len(&self) -> usize {
+        self.strategy.len()
+    }
+
+    #[cfg(test)]
+    fn peek_evict_candidate(&self) -> Option<u64> {
+        self.strategy.peek_evict_candidate()
+    }
+
+    /// Inserts `block`, evicting blocks chosen by the policy until it fits
+    /// within `budget_bytes`. A block that is larger than the budget on its
+    /// own is left out of the cache entirely (read-through, no caching).
+    fn put(&mut self, key: u64, block: Block) {
+        let block_bytes = block.len() as Ulen;
+        if block_bytes > self.budget_bytes {
+            return;
+        }
+        if let Some(previous_bytes) = self.strategy.insert(key, block) {
+            self.current_bytes -= previous_bytes;
+        }
+        self.current_bytes += block_bytes;
+        while self.current_bytes > self.budget_bytes {
+            match self.strategy.evict_one() {
+                Some((_, evicted)) => self.current_bytes -= evicted.len() as Ulen,
+                None => break,
+            }
+        }
+    }
+}
+
+type SharedBlockCache = Arc<Mutex<BlockCache>>;
+
+/// Configuration for [`StoreReader::open_with_cache`]'s hybrid (memory +
+/// disk) decompressed-block cache.
+///
+/// When `disk_path` is `None` this behaves exactly like
+/// [`StoreReader::open_with_cache_budget`]: the disk tier is skipped
+/// entirely.
+pub struct HybridCacheConfig {
+    /// Byte budget for the in-memory tier.
+    pub mem_budget_bytes: Ulen,
+    /// Byte budget for the on-disk tier; oldest files are evicted once it
+    /// is exceeded.
+    pub disk_budget_bytes: Ulen,
+    /// Directory the on-disk tier persists decompressed blocks in. Blocks
+    /// written here outlive the `StoreReader`, so re-opening the same
+    /// store against the same `disk_path` skips re-decompressing blocks
+    /// that are still on disk. Safe to share a single directory across
+    /// every segment: blocks are keyed by `segment_id` as well as
+    /// `checkpoint.start_offset`, so segments can never collide.
+    pub disk_path: Option<PathBuf>,
+    /// Identifies the segment this store belongs to, so a shared
+    /// `disk_path` can't confuse one segment's blocks for another's.
+    pub segment_id: crate::SegmentId,
+}
+
+/// On-disk tier of the hybrid cache: one file per block, named after the
+/// owning segment and the block's `start_offset`, bounded by
+/// `budget_bytes` and evicted oldest-file-first.
+#[derive(Clone)]
+struct DiskBlockCache {
+    dir: PathBuf,
+    segment_id: crate::SegmentId,
+    budget_bytes: Ulen,
+}
+
+impl DiskBlockCache {
+    fn open(
+        dir: PathBuf,
+        segment_id: crate::SegmentId,
+        budget_bytes: Ulen,
+    ) -> io::Result<DiskBlockCache> {
+        std::fs::create_dir_all(&dir)?;
+        Ok(DiskBlockCache {
+            dir,
+            segment_id,
+            budget_bytes,
+        })
+    }
+
+    fn block_path(&self, start_offset: u64) -> PathBuf {
+        self.dir
+            .join(format!("{}-{:020}.block", self.segment_id, start_offset))
+    }
+
+    fn get(&self, start_offset: u64) -> Option<Block> {
+        std::fs::read(self.block_path(start_offset))
+            .ok()
+            .map(Arc::new)
+    }
+
+    /// Persists `block` to disk under `start_offset`, then evicts the
+    /// oldest files on the tier until it is back under `budget_bytes`.
+    fn put(&self, start_offset: u64, block: &Block) -> io::Result<()> {
+        if block.len() as Ulen > self.budget_bytes {
+            return Ok(());
+        }
+        std::fs::write(self.block_path(start_offset), block.as_slice())?;
+        self.evict_to_budget()
+    }
+
+    fn evict_to_budget(&self) -> io::Result<()> {
+        let mut files: Vec<(PathBuf, std::time::SystemTime, Ulen)> =
+            std::fs::read_dir(&self.dir)?
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| {
+                    let metadata = entry.metadata().ok()?;
+                    let modified = metadata.modified().ok()?;
+                    Some((entry.path(), modified, metadata.len() as Ulen))
+                })
+                .collect();
+        let mut total_bytes: Ulen = files.iter().map(|(_, _, len)| *len).sum();
+        if total_bytes <= self.budget_bytes {
+            return Ok(());
+        }
+        files.sort_by_key(|(_, modified, _)| *modified);
+        for (path, _, len) in files {
+            if total_bytes <= self.budget_bytes {
+                break;
+            }
+            std::fs::remove_file(&path)?;
+            total_bytes -= len;
+        }
+        Ok(())
+    }
+}
 
 /// Reads document off tantivy's [`Store`](./index.html)
 pub struct StoreReader {
     data: FileSlice,
-    cache: BlockCache,
+    block_codec: BlockCodec,
+    cache: SharedBlockCache,
+    disk_cache: Option<DiskBlockCache>,
     cache_hits: Arc<AtomicUsize>,
     cache_misses: Arc<AtomicUsize>,
     skip_index: Arc<SkipIndex>,
@@ -31,15 +415,53 @@ pub struct StoreReader {
 }
 
 impl StoreReader {
-    /// Opens a store reader
+    /// Opens a store reader, bounding its decompressed block cache to
+    /// [`DEFAULT_CACHE_BUDGET_BYTES`] and evicting it least-recently-used
+    /// first.
+    ///
+    /// Use [`StoreReader::open_with_cache_budget`] to configure a different
+    /// budget, or [`StoreReader::open_with_cache_policy`] to also pick a
+    /// different [`CacheEvictionPolicy`].
     pub fn open(store_file: FileSlice) -> io::Result<StoreReader> {
-        let (data_file, offset_index_file) = split_file(store_file)?;
+        StoreReader::open_with_cache_budget(store_file, DEFAULT_CACHE_BUDGET_BYTES)
+    }
+
+    /// Opens a store reader whose decompressed block cache is bounded by
+    /// `cache_budget_bytes` rather than by a fixed block count, since blocks
+    /// can range from a few KiB to many MiB depending on doc size.
+    pub fn open_with_cache_budget(
+        store_file: FileSlice,
+        cache_budget_bytes: Ulen,
+    ) -> io::Result<StoreReader> {
+        StoreReader::open_with_cache_policy(
+            store_file,
+            cache_budget_bytes,
+            CacheEvictionPolicy::default(),
+        )
+    }
+
+    /// Opens a store reader with both a byte budget and an eviction policy
+    /// for its decompressed block cache. Skewed access patterns, where a
+    /// small set of documents are fetched far more than others, benefit
+    /// from [`CacheEvictionPolicy::Lfu`] or `WeightedLfu` over the default
+    /// `Lru`.
+    pub fn open_with_cache_policy(
+        store_file: FileSlice,
+        cache_budget_bytes: Ulen,
+        cache_eviction_policy: CacheEvictionPolicy,
+    ) -> io::Result<StoreReader> {
+        let (data_file, offset_index_file, block_codec) = split_file(store_file)?;
         let index_data = offset_index_file.read_bytes()?;
         let space_usage = StoreSpaceUsage::new(data_file.len(), offset_index_file.len());
         let skip_index = SkipIndex::open(index_data);
         Ok(StoreReader {
             data: data_file,
-            cache: Arc::new(Mutex::new(LruCache::new(LRU_CACHE_CAPACITY as usize))),
+            block_codec,
+            cache: Arc::new(Mutex::new(BlockCache::new(
+                cache_budget_bytes,
+                cache_eviction_policy,
+            ))),
+            disk_cache: None,
             cache_hits: Default::default(),
             cache_misses: Default::default(),
             skip_index: Arc::new(skip_index),
@@ -47,6 +469,27 @@ impl StoreReader {
         })
     }
 
+    /// Opens a store reader with a hybrid memory + disk decompressed-block
+    /// cache. Re-opening a segment normally throws away all decompressed
+    /// blocks; pointing `cache_config.disk_path` at a persistent directory
+    /// means hot blocks only ever pay the decompression cost once, even
+    /// across process restarts.
+    pub fn open_with_cache(
+        store_file: FileSlice,
+        cache_config: HybridCacheConfig,
+    ) -> io::Result<StoreReader> {
+        let mut store_reader =
+            StoreReader::open_with_cache_budget(store_file, cache_config.mem_budget_bytes)?;
+        if let Some(disk_path) = cache_config.disk_path {
+            store_reader.disk_cache = Some(DiskBlockCache::open(
+                disk_path,
+                cache_config.segment_id,
+                cache_config.disk_budget_bytes,
+            )?);
+        }
+        Ok(store_reader)
+    }
+
     pub(crate) fn block_checkpoints(&self) -> impl Iterator<Item = Checkpoint> + '_ {
         self.skip_index.checkpoints()
     }
@@ -74,17 +517,38 @@ impl StoreReader {
             return Ok(block.clone());
         }
 
+        if let Some(disk_cache) = &self.disk_cache {
+            if let Some(block) = disk_cache.get(checkpoint.start_offset) {
+                self.cache_hits.fetch_add(1, Ordering::SeqCst);
+                self.cache
+                    .lock()
+                    .unwrap()
+                    .put(checkpoint.start_offset, block.clone());
+                return Ok(block);
+            }
+        }
+
         self.cache_misses.fetch_add(1, Ordering::SeqCst);
 
         let compressed_block = self.compressed_block(checkpoint)?;
         let mut decompressed_block = vec![];
-        decompress(compressed_block.as_slice(), &mut decompressed_block)?;
+        decompress_block(self.block_codec, compressed_block.as_slice(), &mut decompressed_block)?;
 
         let block = Arc::new(decompressed_block);
         self.cache
             .lock()
             .unwrap()
             .put(checkpoint.start_offset, block.clone());
+        if let Some(disk_cache) = &self.disk_cache {
+            // The disk tier is a best-effort optimization: a failure to
+            // persist to it (disk full, permissions, scratch dir removed
+            // concurrently, ...) must not turn an otherwise-successful read
+            // into an error, since the block is already in the in-memory
+            // cache and has been returned to the caller either way.
+            if let Err(err) = disk_cache.put(checkpoint.start_offset, &block) {
+                crate::info_log(format!("failed to persist block to disk cache: {}", err));
+            }
+        }
 
         Ok(block)
     }
@@ -133,14 +597,314 @@ impl StoreReader {
     pub fn space_usage(&self) -> StoreSpaceUsage {
         self.space_usage.clone()
     }
+
+    /// Prefetches and decompresses the blocks holding `doc_ids`, populating
+    /// [`StoreReader`]'s cache so a following [`StoreReader::get`] is a pure
+    /// cache hit.
+    ///
+    /// Unlike [`StoreReader::get_multiple`], this coalesces adjacent or
+    /// near-adjacent checkpoint ranges into a single fetch and issues the
+    /// resulting fetches concurrently, up to [`WARM_BLOCKS_MAX_IN_FLIGHT`]
+    /// at a time, with a bounded retry-with-backoff around each one. That
+    /// makes it a good fit for hiding the latency of a high-latency
+    /// object-store-backed `FileSlice` during a large batch or scan, while
+    /// leaving the hot single-doc `get` path untouched.
+    pub async fn warm_blocks(&self, doc_ids: &[DocId]) -> crate::Result<()> {
+        let mut checkpoints: Vec<Checkpoint> = doc_ids
+            .iter()
+            .flat_map(|doc_id| self.block_checkpoint(*doc_id))
+            .collect();
+        checkpoints.sort_by_key(|checkpoint| checkpoint.start_offset);
+        checkpoints.dedup_by_key(|checkpoint| checkpoint.start_offset);
+        checkpoints.retain(|checkpoint| {
+            // `contains_key`, not `get`: this is a membership probe, not a
+            // real access, and `get` would otherwise bump LFU/WeightedLfu
+            // frequency counters as if the block had genuinely been read.
+            !self
+                .cache
+                .lock()
+                .unwrap()
+                .contains_key(&checkpoint.start_offset)
+        });
+
+        // A block already sitting on the disk tier just needs promoting into
+        // the in-memory cache, the same as a disk-cache hit in `read_block`
+        // does. Pulling it through a full remote fetch + decompress would be
+        // redundant, and leaving it out of the in-memory cache would mean
+        // it's re-read from disk on every future access instead of being
+        // warm in memory too.
+        if let Some(disk_cache) = &self.disk_cache {
+            checkpoints.retain(|checkpoint| match disk_cache.get(checkpoint.start_offset) {
+                Some(block) => {
+                    self.cache.lock().unwrap().put(checkpoint.start_offset, block);
+                    false
+                }
+                None => true,
+            });
+        }
+
+        if checkpoints.is_empty() {
+            return Ok(());
+        }
+
+        let merged_ranges = coalesce_ranges(&checkpoints, WARM_BLOCKS_COALESCE_GAP_BYTES);
+        let semaphore = Arc::new(tokio::sync::Semaphore::new(WARM_BLOCKS_MAX_IN_FLIGHT));
+        let mut handles = Vec::with_capacity(merged_ranges.len());
+        for merged_range in merged_ranges {
+            let checkpoints_in_range: Vec<Checkpoint> = checkpoints
+                .iter()
+                .filter(|checkpoint| {
+                    checkpoint.start_offset as Ulen >= merged_range.start
+                        && checkpoint.end_offset as Ulen <= merged_range.end
+                })
+                .cloned()
+                .collect();
+            let data = self.data.clone();
+            let cache = self.cache.clone();
+            let cache_misses = self.cache_misses.clone();
+            let disk_cache = self.disk_cache.clone();
+            let block_codec = self.block_codec;
+            let semaphore = semaphore.clone();
+            handles.push(tokio::spawn(async move {
+                let _permit = semaphore
+                    .acquire_owned()
+                    .await
+                    .expect("warm_blocks semaphore should never be closed");
+                let fetched = fetch_range_with_retry(&data, merged_range.clone()).await?;
+                for checkpoint in checkpoints_in_range {
+                    // Decompressing from `data` is a cache miss, as in `read_block`.
+                    cache_misses.fetch_add(1, Ordering::SeqCst);
+                    let local_start = (checkpoint.start_offset as Ulen - merged_range.start) as usize;
+                    let local_end = (checkpoint.end_offset as Ulen - merged_range.start) as usize;
+                    let mut decompressed_block = vec![];
+                    decompress_block(
+                        block_codec,
+                        &fetched.as_slice()[local_start..local_end],
+                        &mut decompressed_block,
+                    )?;
+                    let block = Arc::new(decompressed_block);
+                    cache
+                        .lock()
+                        .unwrap()
+                        .put(checkpoint.start_offset, block.clone());
+                    if let Some(disk_cache) = &disk_cache {
+                        // Best-effort, same as `read_block`: losing the disk
+                        // tier write must not fail a warm-up that already
+                        // succeeded in memory.
+                        if let Err(err) = disk_cache.put(checkpoint.start_offset, &block) {
+                            crate::info_log(format!(
+                                "failed to persist warmed block to disk cache: {}",
+                                err
+                            ));
+                        }
+                    }
+                }
+                Ok::<(), io::Error>(())
+            }));
+        }
+
+        for handle in handles {
+            handle
+                .await
+                .map_err(|err| io::Error::new(io::ErrorKind::Other, err))??;
+        }
+        Ok(())
+    }
+}
+
+/// Marks a codec-tag trailer as present, distinguishing it from a legacy
+/// store that ends right after its index data with no trailer at all.
+pub(crate) const CODEC_TAG_MAGIC: [u8; 4] = *b"TSC1";
+
+/// `[codec_tag: 1][zstd_level: 4, little-endian][CODEC_TAG_MAGIC: 4]`,
+/// written right after the index data and before the 8-byte footer that
+/// points at where the index data starts.
+pub(crate) const CODEC_TAG_TRAILER_LEN: Ulen = 9;
+
+/// Compression codec used for a store's decompressed blocks, tagged in the
+/// footer so a store isn't locked to whatever codec happened to be
+/// compiled in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BlockCodec {
+    /// No codec tag was found in the footer: a store written before
+    /// codec tagging existed. Decompressed with the codec this build was
+    /// compiled with, for backward compatibility.
+    Legacy,
+    /// Blocks are stored uncompressed.
+    None,
+    Lz4,
+    Zstd {
+        /// Compression level used when the block was written. Decoding
+        /// zstd does not need it; it is kept around for introspection.
+        level: i32,
+    },
+}
+
+impl BlockCodec {
+    fn from_tag(tag: u8, level: i32) -> io::Result<BlockCodec> {
+        match tag {
+            0 => Ok(BlockCodec::None),
+            1 => Ok(BlockCodec::Lz4),
+            2 => Ok(BlockCodec::Zstd { level }),
+            other => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unknown store block codec tag {}", other),
+            )),
+        }
+    }
+
+    /// Inverse of [`BlockCodec::from_tag`]: the `(tag, zstd_level)` pair a
+    /// writer should encode in the codec-tag trailer for this codec. `Legacy`
+    /// has no tag of its own; a writer should never need to emit one since
+    /// it only ever describes a store written before codec tagging existed.
+    pub(crate) fn to_tag_and_level(self) -> io::Result<(u8, i32)> {
+        match self {
+            BlockCodec::None => Ok((0, 0)),
+            BlockCodec::Lz4 => Ok((1, 0)),
+            BlockCodec::Zstd { level } => Ok((2, level)),
+            BlockCodec::Legacy => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "BlockCodec::Legacy cannot be written, only read",
+            )),
+        }
+    }
 }
 
-fn split_file(data: FileSlice) -> io::Result<(FileSlice, FileSlice)> {
+/// Serializes `codec`'s codec-tag trailer, as read back by
+/// [`split_file`]: `[tag: 1][zstd_level: 4, little-endian][CODEC_TAG_MAGIC: 4]`.
+pub(crate) fn codec_tag_trailer(codec: BlockCodec) -> io::Result<[u8; CODEC_TAG_TRAILER_LEN as usize]> {
+    let (tag, level) = codec.to_tag_and_level()?;
+    let mut trailer = [0u8; CODEC_TAG_TRAILER_LEN as usize];
+    trailer[0] = tag;
+    trailer[1..5].copy_from_slice(&level.to_le_bytes());
+    trailer[5..9].copy_from_slice(&CODEC_TAG_MAGIC);
+    Ok(trailer)
+}
+
+/// Decompresses a `compressed` store block according to `codec`, appending
+/// the result to `decompressed`.
+pub(crate) fn decompress_block(codec: BlockCodec, compressed: &[u8], decompressed: &mut Vec<u8>) -> io::Result<()> {
+    match codec {
+        BlockCodec::Legacy => decompress(compressed, decompressed),
+        BlockCodec::None => {
+            decompressed.extend_from_slice(compressed);
+            Ok(())
+        }
+        BlockCodec::Lz4 => {
+            let block = lz4_flex::decompress_size_prepended(compressed)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            decompressed.extend_from_slice(&block);
+            Ok(())
+        }
+        BlockCodec::Zstd { .. } => {
+            zstd::stream::copy_decode(compressed, decompressed)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+        }
+    }
+}
+
+/// Compresses a store block according to `codec`, appending the result to
+/// `compressed`. The write-side counterpart of [`decompress_block`]; wrapped
+/// by [`super::writer`]'s `compress_store_block`.
+pub(crate) fn compress_block(codec: BlockCodec, block: &[u8], compressed: &mut Vec<u8>) -> io::Result<()> {
+    match codec {
+        BlockCodec::Legacy => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "BlockCodec::Legacy cannot be written, only read",
+        )),
+        BlockCodec::None => {
+            compressed.extend_from_slice(block);
+            Ok(())
+        }
+        BlockCodec::Lz4 => {
+            compressed.extend_from_slice(&lz4_flex::compress_prepend_size(block));
+            Ok(())
+        }
+        BlockCodec::Zstd { level } => {
+            zstd::stream::copy_encode(block, compressed, level)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+        }
+    }
+}
+
+/// Maximum number of [`StoreReader::warm_blocks`] fetches in flight at
+/// once, bounding how hard a single warm-up call hammers the backing
+/// storage.
+const WARM_BLOCKS_MAX_IN_FLIGHT: usize = 8;
+
+/// Checkpoint ranges separated by no more than this many bytes are merged
+/// into a single [`StoreReader::warm_blocks`] fetch, since one larger read
+/// is usually cheaper than several small ones against high-latency object
+/// storage.
+const WARM_BLOCKS_COALESCE_GAP_BYTES: Ulen = 4096;
+
+/// Number of retries [`fetch_range_with_retry`] allows before giving up on
+/// a single range, so a transient object-store error during a large warm-up
+/// doesn't fail the whole batch.
+const WARM_BLOCKS_MAX_RETRIES: u32 = 3;
+
+const WARM_BLOCKS_RETRY_BASE_DELAY: Duration = Duration::from_millis(50);
+
+/// Merges adjacent or near-adjacent `[start_offset, end_offset)` ranges
+/// from `checkpoints` (which must already be sorted by `start_offset`)
+/// into a minimal set of fetch ranges, folding gaps up to
+/// `gap_threshold_bytes` into the same fetch.
+fn coalesce_ranges(checkpoints: &[Checkpoint], gap_threshold_bytes: Ulen) -> Vec<Range<Ulen>> {
+    let mut merged: Vec<Range<Ulen>> = Vec::new();
+    for checkpoint in checkpoints {
+        let range = checkpoint.start_offset as Ulen..checkpoint.end_offset as Ulen;
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end + gap_threshold_bytes => {
+                last.end = last.end.max(range.end);
+            }
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// Fetches `range` from `data`, retrying with exponential backoff up to
+/// [`WARM_BLOCKS_MAX_RETRIES`] times so a transient object-store error
+/// doesn't fail a whole [`StoreReader::warm_blocks`] batch.
+async fn fetch_range_with_retry(data: &FileSlice, range: Range<Ulen>) -> io::Result<OwnedBytes> {
+    let mut attempt = 0;
+    loop {
+        let data = data.clone();
+        let range = range.clone();
+        let result = tokio::task::spawn_blocking(move || data.slice(range.start, range.end).read_bytes())
+            .await
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        match result {
+            Ok(bytes) => return Ok(bytes),
+            Err(_) if attempt < WARM_BLOCKS_MAX_RETRIES => {
+                attempt += 1;
+                tokio::time::sleep(WARM_BLOCKS_RETRY_BASE_DELAY * 2u32.pow(attempt - 1)).await;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+pub(crate) fn split_file(data: FileSlice) -> io::Result<(FileSlice, FileSlice, BlockCodec)> {
     let (data, footer_len_bytes) = data.split_from_end(size_of::<u64>() as Ulen);
     let serialized_offset: OwnedBytes = footer_len_bytes.read_bytes()?;
     let mut serialized_offset_buf = serialized_offset.as_slice();
     let offset = u64::deserialize(&mut serialized_offset_buf)? as Ulen;
-    Ok(data.split(offset))
+
+    if data.len() >= offset + CODEC_TAG_TRAILER_LEN {
+        let (rest, trailer_slice) = data.split_from_end(CODEC_TAG_TRAILER_LEN);
+        let trailer_bytes = trailer_slice.read_bytes()?;
+        let trailer = trailer_bytes.as_slice();
+        if trailer[5..9] == CODEC_TAG_MAGIC {
+            let level = i32::from_le_bytes([trailer[1], trailer[2], trailer[3], trailer[4]]);
+            let codec = BlockCodec::from_tag(trailer[0], level)?;
+            let (data_file, index_file) = rest.split(offset);
+            return Ok((data_file, index_file, codec));
+        }
+    }
+
+    let (data_file, index_file) = data.split(offset);
+    Ok((data_file, index_file, BlockCodec::Legacy))
 }
 
 #[cfg(test)]
@@ -176,12 +940,7 @@ mod tests {
         assert_eq!(store.cache_hits.load(Ordering::SeqCst), 0);
         assert_eq!(store.cache_misses.load(Ordering::SeqCst), 1);
         assert_eq!(
-            store
-                .cache
-                .lock()
-                .unwrap()
-                .peek_lru()
-                .map(|(&k, _)| k as Ulen),
+            store.cache.lock().unwrap().peek_evict_candidate(),
             Some(0)
         );
 
@@ -193,12 +952,7 @@ mod tests {
         assert_eq!(store.cache_misses.load(Ordering::SeqCst), 2);
 
         assert_eq!(
-            store
-                .cache
-                .lock()
-                .unwrap()
-                .peek_lru()
-                .map(|(&k, _)| k as Ulen),
+            store.cache.lock().unwrap().peek_evict_candidate(),
             Some(0)
         );
 
@@ -209,15 +963,352 @@ mod tests {
         assert_eq!(store.cache_hits.load(Ordering::SeqCst), 1);
         assert_eq!(store.cache_misses.load(Ordering::SeqCst), 2);
         assert_eq!(
-            store
-                .cache
-                .lock()
-                .unwrap()
-                .peek_lru()
-                .map(|(&k, _)| k as Ulen),
+            store.cache.lock().unwrap().peek_evict_candidate(),
             Some(18806)
         );
 
         Ok(())
     }
+
+    #[test]
+    fn test_store_cache_budget_eviction() -> crate::Result<()> {
+        let directory = RAMDirectory::create();
+        let path = Path::new("store");
+        let writer = directory.open_write(path)?;
+        write_lorem_ipsum_store(writer, 500);
+        let store_file = directory.open_read(path)?;
+
+        // A budget smaller than a single block's decompressed size still
+        // has to serve the read, it just can't cache it.
+        let store = StoreReader::open_with_cache_budget(store_file.clone(), 1)?;
+        let _ = store.get(0)?;
+        assert_eq!(store.cache.lock().unwrap().len(), 0);
+
+        // A budget that fits roughly one block keeps evicting older ones
+        // as new blocks come in, so the cache never grows unbounded.
+        let store = StoreReader::open_with_cache_budget(store_file, 20_000)?;
+        for doc_id in 0..500 {
+            store.get(doc_id)?;
+        }
+        let cache = store.cache.lock().unwrap();
+        assert!(cache.current_bytes <= 20_000);
+        assert!(cache.len() < 500);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_lfu_cache_keeps_hot_block() -> crate::Result<()> {
+        let directory = RAMDirectory::create();
+        let path = Path::new("store");
+        let writer = directory.open_write(path)?;
+        write_lorem_ipsum_store(writer, 500);
+        let store_file = directory.open_read(path)?;
+        let store = StoreReader::open_with_cache_policy(
+            store_file,
+            20_000,
+            CacheEvictionPolicy::Lfu,
+        )?;
+
+        // Doc 0 is read over and over, so it should never be the eviction
+        // candidate, unlike under a pure LRU policy.
+        for _ in 0..10 {
+            store.get(0)?;
+        }
+        for doc_id in 1..500 {
+            store.get(doc_id)?;
+            assert_ne!(store.cache.lock().unwrap().peek_evict_candidate(), Some(0));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lfu_strategy_evicts_past_first_emptied_bucket() {
+        let mut lfu = LfuStrategy::new(false);
+        let block_a: Block = Arc::new(vec![0u8; 10]);
+        let block_b: Block = Arc::new(vec![0u8; 10]);
+        lfu.insert(1, block_a);
+        lfu.get(1); // Bumps key 1 to frequency 2, emptying bucket 1.
+        lfu.insert(2, block_b); // Key 2 lands in bucket 1 at frequency 1.
+
+        let (evicted_key, _) = lfu.evict_one().expect("key 2 should be evicted first");
+        assert_eq!(evicted_key, 2);
+
+        // Bucket 1 is now empty; `min_frequency` must advance so key 1
+        // (frequency 2) is still found, instead of `evict_one` wrongly
+        // returning `None` while an entry remains.
+        let (evicted_key, _) = lfu.evict_one().expect("key 1 should still be evictable");
+        assert_eq!(evicted_key, 1);
+    }
+
+    #[test]
+    fn test_store_lfu_cache_budget_stays_bounded() -> crate::Result<()> {
+        let directory = RAMDirectory::create();
+        let path = Path::new("store");
+        let writer = directory.open_write(path)?;
+        write_lorem_ipsum_store(writer, 500);
+        let store_file = directory.open_read(path)?;
+        let store =
+            StoreReader::open_with_cache_policy(store_file, 20_000, CacheEvictionPolicy::Lfu)?;
+
+        // Forces many single-occupant-bucket evictions: each doc is read
+        // once then never again, so every bucket empties as soon as it is
+        // evicted from.
+        for doc_id in 0..500 {
+            store.get(doc_id)?;
+        }
+
+        let cache = store.cache.lock().unwrap();
+        assert!(cache.current_bytes <= 20_000);
+        assert!(cache.len() < 500);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_hybrid_cache_survives_reopen() -> crate::Result<()> {
+        let directory = RAMDirectory::create();
+        let path = Path::new("store");
+        let writer = directory.open_write(path)?;
+        let schema = write_lorem_ipsum_store(writer, 500);
+        let title = schema.get_field("title").unwrap();
+        let store_file = directory.open_read(path)?;
+
+        let disk_path = std::env::temp_dir().join(format!(
+            "tantivy-store-hybrid-cache-test-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&disk_path);
+        let segment_id = crate::SegmentId::generate_random();
+
+        let store = StoreReader::open_with_cache(
+            store_file.clone(),
+            HybridCacheConfig {
+                mem_budget_bytes: DEFAULT_CACHE_BUDGET_BYTES,
+                disk_budget_bytes: DEFAULT_CACHE_BUDGET_BYTES,
+                disk_path: Some(disk_path.clone()),
+                segment_id,
+            },
+        )?;
+        let doc = store.get(0)?;
+        assert_eq!(get_text_field(&doc, &title), Some("Doc 0"));
+        assert_eq!(store.cache_misses.load(Ordering::SeqCst), 1);
+
+        // A fresh `StoreReader` pointed at the same `disk_path` should find
+        // the block already decompressed on disk: a cache hit, not a miss.
+        let reopened_store = StoreReader::open_with_cache(
+            store_file,
+            HybridCacheConfig {
+                mem_budget_bytes: DEFAULT_CACHE_BUDGET_BYTES,
+                disk_budget_bytes: DEFAULT_CACHE_BUDGET_BYTES,
+                disk_path: Some(disk_path.clone()),
+                segment_id,
+            },
+        )?;
+        let doc = reopened_store.get(0)?;
+        assert_eq!(get_text_field(&doc, &title), Some("Doc 0"));
+        assert_eq!(reopened_store.cache_hits.load(Ordering::SeqCst), 1);
+        assert_eq!(reopened_store.cache_misses.load(Ordering::SeqCst), 0);
+
+        std::fs::remove_dir_all(&disk_path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_hybrid_cache_keys_by_segment_id() -> crate::Result<()> {
+        let directory = RAMDirectory::create();
+        let path = Path::new("store");
+        let writer = directory.open_write(path)?;
+        let schema = write_lorem_ipsum_store(writer, 500);
+        let title = schema.get_field("title").unwrap();
+        let store_file = directory.open_read(path)?;
+
+        let disk_path = std::env::temp_dir().join(format!(
+            "tantivy-store-hybrid-cache-segment-test-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&disk_path);
+
+        // Two `StoreReader`s for *different* segments sharing the same
+        // `disk_path` must not read back each other's decompressed blocks,
+        // even though both have a checkpoint with `start_offset == 0`.
+        let store_a = StoreReader::open_with_cache(
+            store_file.clone(),
+            HybridCacheConfig {
+                mem_budget_bytes: DEFAULT_CACHE_BUDGET_BYTES,
+                disk_budget_bytes: DEFAULT_CACHE_BUDGET_BYTES,
+                disk_path: Some(disk_path.clone()),
+                segment_id: crate::SegmentId::generate_random(),
+            },
+        )?;
+        let doc = store_a.get(0)?;
+        assert_eq!(get_text_field(&doc, &title), Some("Doc 0"));
+
+        let store_b = StoreReader::open_with_cache(
+            store_file,
+            HybridCacheConfig {
+                mem_budget_bytes: DEFAULT_CACHE_BUDGET_BYTES,
+                disk_budget_bytes: DEFAULT_CACHE_BUDGET_BYTES,
+                disk_path: Some(disk_path.clone()),
+                segment_id: crate::SegmentId::generate_random(),
+            },
+        )?;
+        // `store_b` has never decompressed this block before, so it must be
+        // a cache miss rather than a hit borrowed from `store_a`'s files.
+        let doc = store_b.get(0)?;
+        assert_eq!(get_text_field(&doc, &title), Some("Doc 0"));
+        assert_eq!(store_b.cache_misses.load(Ordering::SeqCst), 1);
+        assert_eq!(store_b.cache_hits.load(Ordering::SeqCst), 0);
+
+        std::fs::remove_dir_all(&disk_path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_file_legacy_has_no_codec_tag() -> crate::Result<()> {
+        let directory = RAMDirectory::create();
+        let path = Path::new("store");
+        let writer = directory.open_write(path)?;
+        write_lorem_ipsum_store(writer, 10);
+        let store_file = directory.open_read(path)?;
+
+        let (_, _, codec) = split_file(store_file)?;
+        assert_eq!(codec, BlockCodec::Legacy);
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_file_reads_codec_tag_trailer() -> crate::Result<()> {
+        let main_data = b"some compressed data";
+        let index_data = b"some skip index data";
+        let offset = main_data.len() as u64;
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(main_data);
+        bytes.extend_from_slice(index_data);
+        bytes.push(2); // BlockCodec::Zstd tag
+        bytes.extend_from_slice(&9i32.to_le_bytes());
+        bytes.extend_from_slice(&CODEC_TAG_MAGIC);
+        offset.serialize(&mut bytes)?;
+
+        let (data_file, index_file, codec) = split_file(FileSlice::from(bytes))?;
+        assert_eq!(codec, BlockCodec::Zstd { level: 9 });
+        assert_eq!(data_file.read_bytes()?.as_slice(), main_data);
+        assert_eq!(index_file.read_bytes()?.as_slice(), index_data);
+        Ok(())
+    }
+
+    #[test]
+    fn test_coalesce_ranges_merges_nearby_checkpoints() {
+        let checkpoints = vec![
+            Checkpoint { start_doc: 0, start_offset: 0, end_offset: 100 },
+            // Within the gap threshold of the previous checkpoint: merged.
+            Checkpoint { start_doc: 10, start_offset: 100 + WARM_BLOCKS_COALESCE_GAP_BYTES as u64, end_offset: 200 + WARM_BLOCKS_COALESCE_GAP_BYTES as u64 },
+            // Far away: a fetch of its own.
+            Checkpoint { start_doc: 20, start_offset: 1_000_000, end_offset: 1_000_100 },
+        ];
+
+        let merged = coalesce_ranges(&checkpoints, WARM_BLOCKS_COALESCE_GAP_BYTES);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0], 0..(200 + WARM_BLOCKS_COALESCE_GAP_BYTES));
+        assert_eq!(merged[1], 1_000_000..1_000_100);
+    }
+
+    #[tokio::test]
+    async fn test_warm_blocks_populates_cache() -> crate::Result<()> {
+        let directory = RAMDirectory::create();
+        let path = Path::new("store");
+        let writer = directory.open_write(path)?;
+        let schema = write_lorem_ipsum_store(writer, 500);
+        let title = schema.get_field("title").unwrap();
+        let store_file = directory.open_read(path)?;
+        let store = StoreReader::open(store_file)?;
+
+        store.warm_blocks(&[0, 499]).await?;
+        assert_eq!(store.cache_hits.load(Ordering::SeqCst), 0);
+        assert_eq!(store.cache_misses.load(Ordering::SeqCst), 2);
+
+        // Both blocks are already warmed, so reading them is a pure hit.
+        let doc = store.get(0)?;
+        assert_eq!(get_text_field(&doc, &title), Some("Doc 0"));
+        assert_eq!(store.cache_hits.load(Ordering::SeqCst), 1);
+        assert_eq!(store.cache_misses.load(Ordering::SeqCst), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_warm_blocks_composes_with_disk_cache() -> crate::Result<()> {
+        let directory = RAMDirectory::create();
+        let path = Path::new("store");
+        let writer = directory.open_write(path)?;
+        let schema = write_lorem_ipsum_store(writer, 500);
+        let title = schema.get_field("title").unwrap();
+        let store_file = directory.open_read(path)?;
+
+        let disk_path = std::env::temp_dir().join(format!(
+            "tantivy-store-warm-blocks-disk-cache-test-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&disk_path);
+        let segment_id = crate::SegmentId::generate_random();
+        let cache_config = || HybridCacheConfig {
+            mem_budget_bytes: DEFAULT_CACHE_BUDGET_BYTES,
+            disk_budget_bytes: DEFAULT_CACHE_BUDGET_BYTES,
+            disk_path: Some(disk_path.clone()),
+            segment_id,
+        };
+
+        let store = StoreReader::open_with_cache(store_file.clone(), cache_config())?;
+        store.warm_blocks(&[0]).await?;
+        assert_eq!(store.cache_misses.load(Ordering::SeqCst), 1);
+
+        // A fresh `StoreReader` over the same segment and `disk_path` should
+        // find doc 0's block already decompressed on disk: `warm_blocks`
+        // must have persisted it there, not just in the first reader's
+        // in-memory cache.
+        let reopened_store = StoreReader::open_with_cache(store_file, cache_config())?;
+        reopened_store.warm_blocks(&[0]).await?;
+        let doc = reopened_store.get(0)?;
+        assert_eq!(get_text_field(&doc, &title), Some("Doc 0"));
+        assert_eq!(reopened_store.cache_hits.load(Ordering::SeqCst), 1);
+        assert_eq!(reopened_store.cache_misses.load(Ordering::SeqCst), 0);
+
+        std::fs::remove_dir_all(&disk_path)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_warm_blocks_does_not_bump_frequency_of_already_warm_blocks() -> crate::Result<()>
+    {
+        let directory = RAMDirectory::create();
+        let path = Path::new("store");
+        let writer = directory.open_write(path)?;
+        write_lorem_ipsum_store(writer, 500);
+        let store_file = directory.open_read(path)?;
+        let store = StoreReader::open_with_cache_policy(store_file, 20_000, CacheEvictionPolicy::Lfu)?;
+
+        // Warm doc 0's block once, then read doc 499 a few times for real.
+        // If `warm_blocks`'s "already warm" check called `get` on doc 0,
+        // re-warming it nine more times would bump its frequency as if it
+        // had been genuinely read just as often as doc 499.
+        store.warm_blocks(&[0]).await?;
+        for _ in 0..9 {
+            store.warm_blocks(&[0]).await?;
+        }
+        for _ in 0..9 {
+            store.get(499)?;
+        }
+        assert_eq!(store.cache_misses.load(Ordering::SeqCst), 2);
+
+        // Doc 0 was only ever warmed, never genuinely read, so it must still
+        // be the lowest-frequency (and thus eviction-candidate) block.
+        assert_eq!(
+            store.cache.lock().unwrap().peek_evict_candidate(),
+            Some(0)
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,120 @@
+//! Write-side counterpart of [`super::reader`]'s codec tagging.
+//!
+//! This only covers picking a [`BlockCodec`] and compressing/tagging blocks
+//! with it. The store writer does not call into this module: the stores it
+//! writes carry no codec-tag trailer and are read back as
+//! [`BlockCodec::Legacy`].
+
+use super::reader::{codec_tag_trailer, compress_block, BlockCodec, CODEC_TAG_TRAILER_LEN};
+use std::io;
+
+/// Compression policy for newly written store blocks, resolved to a
+/// [`BlockCodec`] and tagged in the footer so a reader always decodes with
+/// the codec the writer actually used, regardless of which codec the
+/// reading build defaults to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoreCompression {
+    /// Blocks are stored uncompressed: fastest to write and read, at the
+    /// cost of disk and network footprint.
+    None,
+    /// Lz4: fast to compress and decompress at a modest ratio. The right
+    /// default for hot stores, where blocks are read often and read
+    /// latency matters more than size on disk.
+    Fast,
+    /// Zstd at `level`: slower to compress, a much higher ratio. Suited to
+    /// archival stores that are written once and read rarely, where size on
+    /// disk matters more than write throughput. A high `level` (e.g. 19-22)
+    /// is a reasonable choice here.
+    Archival { level: i32 },
+}
+
+impl StoreCompression {
+    fn as_block_codec(self) -> BlockCodec {
+        match self {
+            StoreCompression::None => BlockCodec::None,
+            StoreCompression::Fast => BlockCodec::Lz4,
+            StoreCompression::Archival { level } => BlockCodec::Zstd { level },
+        }
+    }
+}
+
+/// Compresses `block` according to `compression`, appending the result to
+/// `compressed`.
+pub(crate) fn compress_store_block(
+    compression: StoreCompression,
+    block: &[u8],
+    compressed: &mut Vec<u8>,
+) -> io::Result<()> {
+    compress_block(compression.as_block_codec(), block, compressed)
+}
+
+/// Trailer to append right after the index data and before the 8-byte
+/// footer offset, so [`super::reader::split_file`] tags the store with
+/// `compression` instead of falling back to [`BlockCodec::Legacy`].
+pub(crate) fn store_codec_trailer(
+    compression: StoreCompression,
+) -> io::Result<[u8; CODEC_TAG_TRAILER_LEN as usize]> {
+    codec_tag_trailer(compression.as_block_codec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::BinarySerializable;
+    use crate::directory::FileSlice;
+    use crate::store::reader::{decompress_block, split_file};
+
+    #[test]
+    fn test_compress_store_block_round_trips_through_decompress_block() -> crate::Result<()> {
+        for compression in [
+            StoreCompression::None,
+            StoreCompression::Fast,
+            StoreCompression::Archival { level: 9 },
+        ] {
+            let original = b"some document bytes to compress".repeat(4);
+            let mut compressed = Vec::new();
+            compress_store_block(compression, &original, &mut compressed)?;
+
+            let mut decompressed = Vec::new();
+            decompress_block(compression.as_block_codec(), &compressed, &mut decompressed)?;
+            assert_eq!(decompressed, original);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_codec_trailer_is_read_back_by_split_file() -> crate::Result<()> {
+        let main_data = b"some compressed data";
+        let index_data = b"some skip index data";
+        let offset = main_data.len() as u64;
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(main_data);
+        bytes.extend_from_slice(index_data);
+        bytes.extend_from_slice(&store_codec_trailer(StoreCompression::Archival { level: 9 })?);
+        offset.serialize(&mut bytes)?;
+
+        let (data_file, index_file, codec) = split_file(FileSlice::from(bytes))?;
+        assert_eq!(codec, BlockCodec::Zstd { level: 9 });
+        assert_eq!(data_file.read_bytes()?.as_slice(), main_data);
+        assert_eq!(index_file.read_bytes()?.as_slice(), index_data);
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_compression_none_and_fast_round_trip_trailer() -> crate::Result<()> {
+        for (compression, expected_codec) in [
+            (StoreCompression::None, BlockCodec::None),
+            (StoreCompression::Fast, BlockCodec::Lz4),
+        ] {
+            let offset = 0u64;
+            let mut bytes = Vec::new();
+            bytes.extend_from_slice(&store_codec_trailer(compression)?);
+            offset.serialize(&mut bytes)?;
+
+            let (_, _, codec) = split_file(FileSlice::from(bytes))?;
+            assert_eq!(codec, expected_codec);
+        }
+        Ok(())
+    }
+}